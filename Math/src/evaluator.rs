@@ -51,13 +51,20 @@ ninth token: right_parenthesis(')')
 
 On the Rust convention, I should use Pascal case for struct and enum names, and snake_case for variable and function names.
 */
-enum ExpressionTokens {
+#[derive(Debug)]
+pub(crate) enum ExpressionTokens {
     Number(f64),
     Operator(char),
     Function(String),
     Variable(String),
     LeftParenthesis,
     RightParenthesis,
+    // Separates arguments inside a function call, e.g. the ',' in max(3, 4).
+    Comma,
+    // The '=' in an assignment like `x = 3 + 4`.
+    Assign,
+    // Separates statements, e.g. the ';' in `a = 5; b = a * 2; b + 1`.
+    Semicolon,
 }
 
 /*
@@ -125,28 +132,48 @@ enum ExpressionTokens {
   5. Define the Variable variant to represent variables, including a field for the variable name.
   6. Use Box to allocate child nodes on the heap, enabling recursive structures.
 */
-enum ASTNode {
-    // Leaf node representing a number
-    Number(f64),
-
-    // Node representing a binary operation
+pub(crate) enum ASTNode {
+    // Leaf node representing a number. Keeps the literal's own span so a
+    // later type/range error can point straight back at it.
+    Number(f64, crate::lexer::Span),
+
+    // Node representing a binary operation. The span is the operator
+    // token's own span (not the whole left..right range), so diagnostics
+    // like division-by-zero can point at the '/' itself.
     Operator {
         operator: char,
         left: Box<ASTNode>,
         right: Box<ASTNode>,
+        span: crate::lexer::Span,
     },
-    // Node representing a function call
-    Function {
+    // Node representing a function call. A call can take any number of
+    // comma-separated arguments (including zero, e.g. `foo()`), so the
+    // arguments are kept as a Vec instead of the single boxed argument the
+    // earlier sketch used.
+    FunctionCall {
         name: String,
-        argument: Box<ASTNode>,
+        args: Vec<ASTNode>,
+        span: crate::lexer::Span,
     },
-    // Node representing a variable
-    Variable(String),
+    // Node representing a variable. Keeps the identifier's own span so an
+    // `UndefinedVariable` error can point straight back at it.
+    Variable(String, crate::lexer::Span),
+    // The literals `true`/`false`. The lexer has no separate token for
+    // them (they are plain identifiers), so the parser recognizes these two
+    // names specially instead of looking them up as variables.
+    Boolean(bool),
     // Node representing unary operation
     UnaryOperator {
         operator: char,
         operand: Box<ASTNode>,
     },
+    // Node representing an assignment, e.g. `x = 3 + 4`. Only meaningful at
+    // the top level of a statement, evaluated through
+    // `evaluate_with_context_mut` rather than the plain `evaluate`.
+    Assignment {
+        name: String,
+        value: Box<ASTNode>,
+    },
 }
 
 /*
@@ -163,20 +190,99 @@ enum ASTNode {
    3 -> Return values using their keys.
 
 */
-struct EvaluationContext {
-    variables: HashMap<String, f64>,
-    functions: HashMap<String, fn(Vec<f64>) -> f64>,
+pub(crate) struct EvaluationContext {
+    variables: HashMap<String, Value>,
+    functions: HashMap<String, Function>,
+}
+
+// A builtin callable plus the number of arguments it expects, so a call
+// site like `sin(1, 2)` can be rejected before the closure ever sees a
+// mis-sized `Vec`.
+//   - arity: Some(n) -> the function takes exactly n arguments.
+//   - arity: None -> the function is variadic and accepts any count.
+#[derive(Clone, Copy)]
+pub(crate) struct Function {
+    arity: Option<usize>,
+    callable: fn(Vec<f64>) -> f64,
+}
+
+/*
+  A runtime value. Expressions no longer collapse everything to `f64`:
+   - Int(i64) -> a whole number, e.g. the result of `3 + 4`.
+   - Float(f64) -> a number with a fractional part, or anything mixed with
+     one, e.g. the result of `3 + 4.5`.
+   - Boolean(bool) -> the result of a comparison (`>`, `<`, `==`) or a
+     logical combination (`&&`, `||`).
+   - Unit -> the "nothing" produced by an assignment statement, e.g. the
+     `a = 5` in `a = 5; a + 1` has no value of its own worth printing.
+  Arithmetic on two `Int`s stays `Int`; mixing `Int` and `Float` promotes
+  the whole expression to `Float`, the same rule most typed languages use.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Value {
+    Int(i64),
+    Float(f64),
+    Boolean(bool),
+    Unit,
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::Boolean(_) => "boolean",
+            Value::Unit => "unit",
+        }
+    }
+
+    // Widens an Int/Float value to f64 for arithmetic that always needs a
+    // float result (division, exponentiation, comparisons). Errors on a
+    // Boolean or Unit, since neither has a sensible numeric meaning.
+    fn as_f64(&self) -> Result<f64, EvaluationError> {
+        match self {
+            Value::Int(n) => Ok(*n as f64),
+            Value::Float(n) => Ok(*n),
+            Value::Boolean(_) | Value::Unit => Err(EvaluationError::TypeError {
+                expected: "int or float".to_string(),
+                found: self.type_name().to_string(),
+            }),
+        }
+    }
+
+    fn as_bool(&self) -> Result<bool, EvaluationError> {
+        match self {
+            Value::Boolean(b) => Ok(*b),
+            _ => Err(EvaluationError::TypeError {
+                expected: "boolean".to_string(),
+                found: self.type_name().to_string(),
+            }),
+        }
+    }
+}
+
+// Renders a `Value` the way a reduction-trace step wants it, e.g. the `-6`
+// in `"2 - 8 = -6"` (see `evaluate`'s step-building in the `Operator` arm).
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Float(n) => write!(f, "{}", n),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::Unit => write!(f, "()"),
+        }
+    }
 }
 
 /*
   Here we should define what is the end result of the evaluation or better the direction we want to go with it.
 
-  We have value: f64 -> the final evaluated result of the expression.
+  We have value: Value -> the final evaluated result of the expression.
   steps: Vec<String> -> a vector of strings representing the steps taken during the evaluation process.
   errors: Vec<String> -> a vector of strings representing any errors encountered during the evaluation process
 */
-struct DetailedEvaluationResult {
-    value: Result<f64, EvaluationError>,
+pub(crate) struct DetailedEvaluationResult {
+    pub(crate) value: Result<Value, EvaluationError>,
     steps: Vec<String>,
 }
 
@@ -186,13 +292,39 @@ struct DetailedEvaluationResult {
    1 - DivisionByZero -> error when there is an attempt to divide by zero.
    2 - UndefinedVariable(String) -> error when a variable used in the expression is not defined in the context.
    3 - UndefinedFunction(String) -> error when a function used in the expression is not defined in the context.
+  Each of the three above also carries the `span` of the offending token, so
+  the REPL can point a caret straight at it instead of just naming it.
    4 - SyntaxError(String) -> error when there is a syntax error in the expression being evaluated.
+   5 - TypeError{expected, found} -> error when an operator gets operands of
+       incompatible types, e.g. `true * 3` (expected "int or float", found "boolean").
+   6 - WrongArgumentCount{name, expected, found} -> error when a call site
+       passes a different number of arguments than the function's arity,
+       e.g. `sqrt(1, 2)` (expected 1, found 2).
 */
-enum EvaluationError {
-    DivisionByZero,
-    UndefinedVariable(String),
-    UndefinedFunction(String),
+#[derive(Debug)]
+pub(crate) enum EvaluationError {
+    DivisionByZero { span: crate::lexer::Span },
+    UndefinedVariable { name: String, span: crate::lexer::Span },
+    UndefinedFunction { name: String, span: crate::lexer::Span },
     SyntaxError(String),
+    TypeError { expected: String, found: String },
+    WrongArgumentCount { name: String, expected: usize, found: usize },
+}
+
+impl EvaluationError {
+    // The span to put a caret under, for the variants that carry one. The
+    // others (`SyntaxError`, `TypeError`, `WrongArgumentCount`) aren't tied
+    // to a single token in the source, so they have none.
+    pub(crate) fn span(&self) -> Option<crate::lexer::Span> {
+        match self {
+            EvaluationError::DivisionByZero { span } => Some(*span),
+            EvaluationError::UndefinedVariable { span, .. } => Some(*span),
+            EvaluationError::UndefinedFunction { span, .. } => Some(*span),
+            EvaluationError::SyntaxError(_) => None,
+            EvaluationError::TypeError { .. } => None,
+            EvaluationError::WrongArgumentCount { .. } => None,
+        }
+    }
 }
 
 
@@ -208,22 +340,22 @@ enum EvaluationError {
 *
 * */
 impl EvaluationContext {
-    fn new(variables: HashMap<String, f64>, functions: HashMap<String, fn(Vec<f64>) -> f64>) -> Self {      
+    pub(crate) fn new(variables: HashMap<String, Value>, functions: HashMap<String, Function>) -> Self {
         Self {variables, functions}
     }
 
-    fn set_variable(&mut self, variable: String, value: f64) {
+    fn set_variable(&mut self, variable: String, value: Value) {
         self.variables.insert(variable, value);
     }
 
-    fn set_function(&mut self, function: String, value: fn(Vec<f64>) -> f64) {
-        self.functions.insert(function, value);
+    pub(crate) fn set_function(&mut self, function: String, arity: Option<usize>, callable: fn(Vec<f64>) -> f64) {
+        self.functions.insert(function, Function { arity, callable });
     }
 
-    fn get_variable(&self, variable: &str) -> Option<f64>{
+    fn get_variable(&self, variable: &str) -> Option<Value>{
         self.variables.get(variable).copied()
     }
-    fn get_function(&self, function: &str) -> Option<fn(Vec<f64>) -> f64> {
+    fn get_function(&self, function: &str) -> Option<Function> {
         self.functions.get(function).copied()
     }
 
@@ -231,7 +363,13 @@ impl EvaluationContext {
 
 
 impl DetailedEvaluationResult {
-    fn ok(value: f64) -> Self {
+    // The reduction trace, in evaluation order, e.g. `["2 - 8 = -6", "5 * -6
+    // = -30", "3 + -30 = -27"]` for `3 + 5 * (2 - 8)`.
+    pub(crate) fn steps(&self) -> &[String] {
+        &self.steps
+    }
+
+    fn ok(value: Value) -> Self {
         Self { value: Ok(value), steps: Vec::new() }
     }
     fn err(error: EvaluationError) -> Self {
@@ -247,19 +385,312 @@ impl DetailedEvaluationResult {
     }
 }
 
+// Combines two Int/Float operands for `+`, `-`, `*`: both `Int` stays
+// `Int`; anything else promotes to `Float`. `/` and `^` always produce a
+// `Float`, since integer division/exponentiation would either truncate
+// silently or overflow for no real benefit in a calculator.
+fn apply_arithmetic(operator: char, left: Value, right: Value, span: crate::lexer::Span) -> Result<Value, EvaluationError> {
+    if let ('+' | '-' | '*', Value::Int(a), Value::Int(b)) = (operator, left, right) {
+        return Ok(Value::Int(match operator {
+            '+' => a + b,
+            '-' => a - b,
+            '*' => a * b,
+            _ => unreachable!(),
+        }));
+    }
+
+    let a = left.as_f64()?;
+    let b = right.as_f64()?;
+    match operator {
+        '+' => Ok(Value::Float(a + b)),
+        '-' => Ok(Value::Float(a - b)),
+        '*' => Ok(Value::Float(a * b)),
+        '/' if b == 0.0 => Err(EvaluationError::DivisionByZero { span }),
+        '/' => Ok(Value::Float(a / b)),
+        '^' => Ok(Value::Float(a.powf(b))),
+        '>' => Ok(Value::Boolean(a > b)),
+        '<' => Ok(Value::Boolean(a < b)),
+        _ => Err(EvaluationError::SyntaxError(format!("unknown operator '{}'", operator))),
+    }
+}
+
+// The human-readable spelling of an operator token, for reduction-trace
+// steps (see `evaluate`'s `Operator` arm). The lexer folds two-char
+// operators like `==`/`&&`/`||` down to a single `char` (see
+// `lexer::next_token`), so this is also where that char is expanded back
+// out for display.
+fn operator_symbol(operator: char) -> &'static str {
+    match operator {
+        '=' => "==",
+        '&' => "&&",
+        '|' => "||",
+        '+' => "+",
+        '-' => "-",
+        '*' => "*",
+        '/' => "/",
+        '^' => "^",
+        '>' => ">",
+        '<' => "<",
+        _ => "?",
+    }
+}
+
 fn evaluate(node: &ASTNode, context: &EvaluationContext) -> DetailedEvaluationResult {
     match node {
-        ASTNode::Number(n) => {
-            DetailedEvaluationResult::ok(*n)
+        // A bare numeric literal doesn't yet carry whether it was written
+        // with a decimal point (the lexer folds `3` and `3.0` into the same
+        // f64), so an integral value is treated as `Int` and anything with
+        // a fractional part as `Float`.
+        ASTNode::Number(n, _span) => {
+            if n.fract() == 0.0 {
+                DetailedEvaluationResult::ok(Value::Int(*n as i64))
+            } else {
+                DetailedEvaluationResult::ok(Value::Float(*n))
+            }
         },
-        ASTNode::Variable(name) => {
+        ASTNode::Boolean(b) => {
+            DetailedEvaluationResult::ok(Value::Boolean(*b))
+        }
+        ASTNode::Variable(name, span) => {
             if let Some(value) = context.get_variable(name) {
                 DetailedEvaluationResult::ok(value)
             } else {
-                DetailedEvaluationResult::err(EvaluationError::UndefinedVariable(name.clone()))
+                DetailedEvaluationResult::err(EvaluationError::UndefinedVariable { name: name.clone(), span: *span })
             }
 
         }
-        _ => todo!()
+        ASTNode::UnaryOperator { operator, operand } => {
+            let operand_result = evaluate(operand, context);
+            let value = match operand_result.value {
+                Ok(value) => value,
+                Err(error) => return DetailedEvaluationResult::err(error).with_steps(operand_result.steps),
+            };
+
+            let outcome = match operator {
+                '-' => match value {
+                    Value::Int(n) => Ok(Value::Int(-n)),
+                    _ => value.as_f64().map(|n| Value::Float(-n)),
+                },
+                '+' => value.as_f64().map(|_| value),
+                _ => Err(EvaluationError::SyntaxError(format!("unknown unary operator '{}'", operator))),
+            };
+
+            match outcome {
+                Ok(result) => DetailedEvaluationResult::ok(result)
+                    .with_steps(operand_result.steps)
+                    .with_step(format!("{}{} = {}", operator, value, result)),
+                Err(error) => DetailedEvaluationResult::err(error).with_steps(operand_result.steps),
+            }
+        }
+        ASTNode::Operator { operator, left, right, span } => {
+            let left_result = evaluate(left, context);
+            let left = match left_result.value {
+                Ok(value) => value,
+                Err(error) => return DetailedEvaluationResult::err(error).with_steps(left_result.steps),
+            };
+            let right_result = evaluate(right, context);
+            let right = match right_result.value {
+                Ok(value) => value,
+                Err(error) => {
+                    return DetailedEvaluationResult::err(error)
+                        .with_steps(left_result.steps)
+                        .with_steps(right_result.steps)
+                }
+            };
+            let child_steps = left_result.steps.into_iter().chain(right_result.steps).collect::<Vec<_>>();
+
+            let outcome = match operator {
+                '+' | '-' | '*' | '/' | '^' | '>' | '<' => apply_arithmetic(*operator, left, right, *span),
+                // '=' here stands for the lexer's `==` token; '&'/'|' stand
+                // for `&&`/`||` (see lexer::next_token for why they share a
+                // single-char Operator token with the arithmetic operators).
+                '=' => Ok(Value::Boolean(left == right)),
+                '&' | '|' => left.as_bool().and_then(|a| {
+                    right.as_bool().map(|b| Value::Boolean(if *operator == '&' { a && b } else { a || b }))
+                }),
+                _ => Err(EvaluationError::SyntaxError(format!("unknown operator '{}'", operator))),
+            };
+
+            match outcome {
+                Ok(result) => DetailedEvaluationResult::ok(result)
+                    .with_steps(child_steps)
+                    .with_step(format!("{} {} {} = {}", left, operator_symbol(*operator), right, result)),
+                Err(error) => DetailedEvaluationResult::err(error).with_steps(child_steps),
+            }
+        }
+        ASTNode::FunctionCall { name, args, span } => {
+            let function = match context.get_function(name) {
+                Some(function) => function,
+                None => {
+                    return DetailedEvaluationResult::err(EvaluationError::UndefinedFunction {
+                        name: name.clone(),
+                        span: *span,
+                    })
+                }
+            };
+
+            if let Some(expected) = function.arity {
+                if expected != args.len() {
+                    return DetailedEvaluationResult::err(EvaluationError::WrongArgumentCount {
+                        name: name.clone(),
+                        expected,
+                        found: args.len(),
+                    });
+                }
+            }
+
+            let mut values = Vec::with_capacity(args.len());
+            let mut child_steps = Vec::new();
+            for arg in args {
+                let arg_result = evaluate(arg, context);
+                child_steps.extend(arg_result.steps);
+                match arg_result.value {
+                    Ok(value) => match value.as_f64() {
+                        Ok(n) => values.push(n),
+                        Err(error) => return DetailedEvaluationResult::err(error).with_steps(child_steps),
+                    },
+                    Err(error) => return DetailedEvaluationResult::err(error).with_steps(child_steps),
+                }
+            }
+
+            let formatted_args = values.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ");
+            let result = Value::Float((function.callable)(values));
+            DetailedEvaluationResult::ok(result)
+                .with_steps(child_steps)
+                .with_step(format!("{}({}) = {}", name, formatted_args, result))
+        }
+        ASTNode::Assignment { .. } => {
+            DetailedEvaluationResult::err(EvaluationError::SyntaxError(
+                "assignment is only valid as a top-level statement".to_string(),
+            ))
+        }
+    }
+}
+
+// Evaluates a sequence of top-level statements against a *mutable* context,
+// e.g. the statements produced by splitting `a = 5; b = a * 2; b + 1` on its
+// `;` separators. This is the entry point the REPL uses: unlike `evaluate`,
+// it understands `ASTNode::Assignment`, writing the assigned value back into
+// `context` so later statements (and later REPL lines) can see it.
+//
+// The result is that of the last statement; an assignment's own result is
+// `Value::Unit`, so a script that ends in `b = a * 2` reports `Unit` rather
+// than `a * 2`'s value.
+pub(crate) fn evaluate_with_context_mut(nodes: &[ASTNode], context: &mut EvaluationContext) -> DetailedEvaluationResult {
+    let mut last = DetailedEvaluationResult::ok(Value::Unit);
+    for node in nodes {
+        last = match node {
+            ASTNode::Assignment { name, value } => {
+                let result = evaluate(value, context);
+                match result.value {
+                    Ok(value) => {
+                        context.set_variable(name.clone(), value);
+                        DetailedEvaluationResult::ok(Value::Unit).with_steps(result.steps)
+                    }
+                    Err(error) => DetailedEvaluationResult::err(error).with_steps(result.steps),
+                }
+            }
+            other => evaluate(other, context),
+        };
+
+        if last.value.is_err() {
+            return last;
+        }
+    }
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Span;
+
+    fn context_with_function(name: &str, arity: Option<usize>, callable: fn(Vec<f64>) -> f64) -> EvaluationContext {
+        let mut context = EvaluationContext::new(HashMap::new(), HashMap::new());
+        context.set_function(name.to_string(), arity, callable);
+        context
+    }
+
+    fn number(n: f64) -> ASTNode {
+        ASTNode::Number(n, Span::new(0))
+    }
+
+    #[test]
+    fn wrong_argument_count_is_rejected_before_the_call() {
+        let context = context_with_function("sqrt", Some(1), |args| args[0].sqrt());
+        let call = ASTNode::FunctionCall { name: "sqrt".to_string(), args: vec![number(1.0), number(2.0)], span: Span::new(0) };
+
+        match evaluate(&call, &context).value {
+            Err(EvaluationError::WrongArgumentCount { expected: 1, found: 2, .. }) => {}
+            other => panic!("expected WrongArgumentCount {{ expected: 1, found: 2 }}, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn matching_argument_count_calls_the_function() {
+        let context = context_with_function("sqrt", Some(1), |args| args[0].sqrt());
+        let call = ASTNode::FunctionCall { name: "sqrt".to_string(), args: vec![number(16.0)], span: Span::new(0) };
+
+        match evaluate(&call, &context).value {
+            Ok(value) => assert_eq!(value, Value::Float(4.0)),
+            other => panic!("expected Ok(Value::Float(4.0)), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn variadic_function_accepts_any_argument_count() {
+        let context = context_with_function("sum", None, |args| args.iter().sum());
+        let call = ASTNode::FunctionCall { name: "sum".to_string(), args: vec![number(1.0), number(2.0), number(3.0)], span: Span::new(0) };
+
+        match evaluate(&call, &context).value {
+            Ok(value) => assert_eq!(value, Value::Float(6.0)),
+            other => panic!("expected Ok(Value::Float(6.0)), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn zero_arity_function_rejects_any_arguments() {
+        let context = context_with_function("pi", Some(0), |_| std::f64::consts::PI);
+        let call = ASTNode::FunctionCall { name: "pi".to_string(), args: vec![number(1.0)], span: Span::new(0) };
+
+        match evaluate(&call, &context).value {
+            Err(EvaluationError::WrongArgumentCount { expected: 0, found: 1, .. }) => {}
+            other => panic!("expected WrongArgumentCount {{ expected: 0, found: 1 }}, got {:?}", other),
+        }
+    }
+
+    // `3 + 5 * (2 - 8)`, built by hand the way the parser would assemble it:
+    //           (+)
+    //          /   \
+    //        (3)   (*)
+    //              /   \
+    //            (5)   (-)
+    //                  /   \
+    //                (2)   (8)
+    #[test]
+    fn steps_record_each_reduction_in_evaluation_order() {
+        let context = EvaluationContext::new(HashMap::new(), HashMap::new());
+        let expr = ASTNode::Operator {
+            operator: '+',
+            left: Box::new(number(3.0)),
+            right: Box::new(ASTNode::Operator {
+                operator: '*',
+                left: Box::new(number(5.0)),
+                right: Box::new(ASTNode::Operator {
+                    operator: '-',
+                    left: Box::new(number(2.0)),
+                    right: Box::new(number(8.0)),
+                    span: Span::new(0),
+                }),
+                span: Span::new(0),
+            }),
+            span: Span::new(0),
+        };
+
+        let result = evaluate(&expr, &context);
+        assert_eq!(
+            result.steps(),
+            ["2 - 8 = -6", "5 * -6 = -30", "3 + -30 = -27"],
+        );
     }
 }
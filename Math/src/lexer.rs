@@ -4,6 +4,46 @@
 
 use crate::evaluator::ExpressionTokens;
 
+/*
+  A Span pins a point in the original source string so an error can say
+  *where* it went wrong, not just what. For now it is a single byte offset;
+  once tokens carry their own spans (see the AST) this also becomes the
+  basis for pointing at a whole token or node instead of one character.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub offset: usize,
+}
+
+impl Span {
+    pub fn new(offset: usize) -> Self {
+        Span { offset }
+    }
+
+    // Turns the byte offset into a 0-indexed (line, column) pair by walking
+    // the source and accumulating `line.len() + 1` (the `+ 1` accounts for
+    // the '\n' that `split('\n')` strips) per line until the running total
+    // would pass `self.offset`.
+    pub fn linecol_in(&self, src: &str) -> (usize, usize) {
+        let mut consumed = 0;
+        for (line_no, line) in src.split('\n').enumerate() {
+            let line_len = line.len() + 1;
+            if consumed + line_len > self.offset {
+                return (line_no, self.offset - consumed);
+            }
+            consumed += line_len;
+        }
+        (0, self.offset)
+    }
+}
+
+// Everything that can go wrong while turning raw source text into tokens.
+#[derive(Debug)]
+pub enum LexError {
+    UnexpectedChar { ch: char, span: Span },
+    InvalidNumber { raw: String, span: Span },
+}
+
 /*
   The lexer job is to take the raw input string and convert it into a series of tokens that can be easily processed by the parser.
 
@@ -88,6 +128,19 @@ impl Lexer {
     fn peek(&self) -> Option<char> {
         self.input.chars().nth(self.position)
     }
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.input.chars().nth(self.position + offset)
+    }
+    // Looks ahead to the next non-whitespace character without consuming
+    // anything, so an identifier can decide whether it names a function
+    // (immediately followed by '(', ignoring space in between) or a plain
+    // variable, without the lexer having to backtrack.
+    fn peek_non_whitespace(&self) -> Option<char> {
+        self.input
+            .chars()
+            .skip(self.position)
+            .find(|ch| !ch.is_whitespace())
+    }
     fn advance(&mut self) {
         self.position += 1;
     }
@@ -107,39 +160,79 @@ impl Lexer {
 
     */
     fn skip_whitespace(&mut self) {
-        while self.peek().map_or(false, |ch| ch.is_whitespace()) {
+        while self.peek().is_some_and(|ch| ch.is_whitespace()) {
             self.advance();
         }
     }
 
-    /*
-      The idea here is to run read_number to check if the char is number or dot and return false if not on the while block.
-      Given that I cannot access the context of the ch of the map_or, given it only exist there, I may access the ch from the peek method itself, if exist, he pushes the ch in the string, appeding the value.
+    // Consumes characters matching `pred`, skipping `_` digit separators,
+    // and returns the accumulated (separator-free) string.
+    fn consume_digits(&mut self, pred: impl Fn(char) -> bool) -> String {
+        let mut digits = String::new();
+        while self.peek().is_some_and(|ch| pred(ch) || ch == '_') {
+            if let Some(ch) = self.peek() {
+                if ch != '_' {
+                    digits.push(ch);
+                }
+            }
+            self.advance();
+        }
+        digits
+    }
 
-      After that advance until the end of the while loop.
+    // Reads a numeric literal starting at the current position. Beyond the
+    // plain `123` / `3.14` the original version handled, this also accepts:
+    //   - a `0x`/`0b` prefix for hex/binary integers (`0xFF`, `0b1010`)
+    //   - a scientific-notation exponent (`1e9`, `6.02e-23`)
+    //   - `_` as a digit separator anywhere in the literal (`1_000_000`)
+    // Returns a `LexError::InvalidNumber` instead of panicking on a
+    // malformed literal such as an empty hex body (`0x`).
+    fn read_number(&mut self) -> Result<f64, LexError> {
+        let start = self.position;
+
+        if self.peek() == Some('0') && matches!(self.peek_at(1), Some('x') | Some('X')) {
+            self.advance();
+            self.advance();
+            let digits = self.consume_digits(|ch| ch.is_ascii_hexdigit());
+            return i64::from_str_radix(&digits, 16).map(|n| n as f64).map_err(|_| {
+                LexError::InvalidNumber { raw: self.input[start..self.position].to_string(), span: Span::new(start) }
+            });
+        }
 
-      The method read_number expects an return of the type f64, to achive so, I must get the method parse of the type String.
+        if self.peek() == Some('0') && matches!(self.peek_at(1), Some('b') | Some('B')) {
+            self.advance();
+            self.advance();
+            let digits = self.consume_digits(|ch| ch == '0' || ch == '1');
+            return i64::from_str_radix(&digits, 2).map(|n| n as f64).map_err(|_| {
+                LexError::InvalidNumber { raw: self.input[start..self.position].to_string(), span: Span::new(start) }
+            });
+        }
 
-      The method parse works like this:
+        let mut raw = self.consume_digits(|ch| ch.is_ascii_digit());
 
-      thestring.parse::<type I wanto to parse into>.unwrap()
-      ::<> -> this sintaxe is called turbofish, it indicates directly to the program the type you wanto to parse into.
-      unwrap() -> this method helps us to retrive the value parsed directly(Some) and return a panic if the result is the type None.
-    */
-    fn read_number(&mut self) -> f64 {
-        let mut valores = String::new();
+        if self.peek() == Some('.') && self.peek_at(1).is_some_and(|ch| ch.is_ascii_digit()) {
+            self.advance();
+            raw.push('.');
+            raw.push_str(&self.consume_digits(|ch| ch.is_ascii_digit()));
+        }
 
-        while self
-            .peek()
-            .map_or(false, |ch| ch.is_ascii_digit() || ch == '.')
-        {
-            if let Some(ch) = self.peek() {
-                valores.push(ch);
+        if matches!(self.peek(), Some('e') | Some('E')) {
+            let mut exponent = String::new();
+            exponent.push(self.peek().expect("checked above"));
+            self.advance();
+            if matches!(self.peek(), Some('+') | Some('-')) {
+                exponent.push(self.peek().expect("checked above"));
+                self.advance();
+            }
+            let exponent_digits = self.consume_digits(|ch| ch.is_ascii_digit());
+            if exponent_digits.is_empty() {
+                return Err(LexError::InvalidNumber { raw: self.input[start..self.position].to_string(), span: Span::new(start) });
             }
-            self.advance()
+            exponent.push_str(&exponent_digits);
+            raw.push_str(&exponent);
         }
 
-        valores.parse::<f64>().unwrap()
+        raw.parse::<f64>().map_err(|_| LexError::InvalidNumber { raw: raw.clone(), span: Span::new(start) })
     }
 
     fn read_identifier(&mut self) -> String {
@@ -147,7 +240,7 @@ impl Lexer {
 
         while self
             .peek()
-            .map_or(false, |ch| ch.is_alphanumeric() || ch == '_')
+            .is_some_and(|ch| ch.is_alphanumeric() || ch == '_')
         {
             if let Some(ch) = self.peek() {
                 identificador.push(ch);
@@ -201,35 +294,81 @@ impl Lexer {
      * But I decided to leave to None.
      *
      * */
-    fn next_token(&mut self) -> Option<ExpressionTokens> {
+    fn next_token(&mut self) -> Result<Option<(ExpressionTokens, Span)>, LexError> {
         self.skip_whitespace();
 
-        let ch = self.peek()?;
+        // Captured after skip_whitespace so the span points at the first
+        // character of the token itself, not any leading whitespace.
+        let start = self.position;
+
+        let ch = match self.peek() {
+            Some(ch) => ch,
+            None => return Ok(None),
+        };
 
-        match ch {
+        let token = match ch {
             '0'..='9' => {
-                return Some(ExpressionTokens::Number(self.read_number()));
+                ExpressionTokens::Number(self.read_number()?)
             }
             '(' => {
                 self.advance();
-                Some(ExpressionTokens::LeftParenthesis)
+                ExpressionTokens::LeftParenthesis
             }
             ')' => {
                 self.advance();
-                Some(ExpressionTokens::RightParenthesis)
+                ExpressionTokens::RightParenthesis
+            }
+            '+' | '-' | '*' | '/' | '^' | '>' | '<' => {
+                self.advance();
+                ExpressionTokens::Operator(ch)
+            }
+            ',' => {
+                self.advance();
+                ExpressionTokens::Comma
+            }
+            // Separates statements, e.g. `a = 5; b = a * 2; b + 1`.
+            ';' => {
+                self.advance();
+                ExpressionTokens::Semicolon
+            }
+            // A lone '=' is assignment; '==' is the equality operator. Both
+            // '&&' and '||' need their second character too -- a lone '&'
+            // or '|' has no meaning in this grammar, so it is an error.
+            '=' if self.peek_at(1) == Some('=') => {
+                self.advance();
+                self.advance();
+                ExpressionTokens::Operator('=')
+            }
+            '=' => {
+                self.advance();
+                ExpressionTokens::Assign
+            }
+            '&' if self.peek_at(1) == Some('&') => {
+                self.advance();
+                self.advance();
+                ExpressionTokens::Operator('&')
             }
-            '+' | '-' | '*' | '/' => {
+            '|' if self.peek_at(1) == Some('|') => {
                 self.advance();
-                Some(ExpressionTokens::Operator(ch))
+                self.advance();
+                ExpressionTokens::Operator('|')
             }
             'a'..='z' | 'A'..='Z' | '_' => {
-                return Some(ExpressionTokens::Variable(self.read_identifier()));
+                let name = self.read_identifier();
+                if self.peek_non_whitespace() == Some('(') {
+                    ExpressionTokens::Function(name)
+                } else {
+                    ExpressionTokens::Variable(name)
+                }
             }
             _ => {
+                let span = Span::new(start);
                 self.advance();
-                None
+                return Err(LexError::UnexpectedChar { ch, span });
             }
-        }
+        };
+
+        Ok(Some((token, Span::new(start))))
     }
 
     /*
@@ -244,13 +383,60 @@ impl Lexer {
      *           collection. (https://doc.rust-lang.org/std/vec/struct.Vec.html#method.push)
      *   3 - At the end, return a vec of tokens like this: ['(','3','+','8',')','+','5']
      * */
-    pub fn tokenize(&mut self) -> Vec<ExpressionTokens> {
+    pub fn tokenize(&mut self) -> Result<Vec<(ExpressionTokens, Span)>, LexError> {
         let mut tokens = Vec::new();
 
-        while let Some(token) = self.next_token() {
+        while let Some(token) = self.next_token()? {
             tokens.push(token);
         }
 
-        tokens
+        Ok(tokens)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(input: &str) -> Vec<f64> {
+        Lexer::new(input.to_string())
+            .tokenize()
+            .unwrap()
+            .into_iter()
+            .filter_map(|(token, _)| match token {
+                ExpressionTokens::Number(n) => Some(n),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_hex_and_binary_literals() {
+        assert_eq!(numbers("0xFF"), vec![255.0]);
+        assert_eq!(numbers("0b1010"), vec![10.0]);
+    }
+
+    #[test]
+    fn reads_scientific_notation() {
+        assert_eq!(numbers("6.02e-23"), vec![6.02e-23]);
+        assert_eq!(numbers("1e9"), vec![1e9]);
+    }
+
+    #[test]
+    fn reads_underscore_separated_literals() {
+        assert_eq!(numbers("1_000_000"), vec![1_000_000.0]);
+    }
+
+    #[test]
+    fn rejects_empty_hex_body() {
+        let error = Lexer::new("0x".to_string()).tokenize().unwrap_err();
+        assert!(matches!(error, LexError::InvalidNumber { .. }));
+    }
+
+    #[test]
+    fn rejects_incomplete_exponent() {
+        let error = Lexer::new("1e".to_string()).tokenize().unwrap_err();
+        assert!(matches!(error, LexError::InvalidNumber { .. }));
+    }
+}
+
@@ -2,27 +2,115 @@ mod evaluator;
 mod lexer;
 mod parser;
 
-use evaluator::{EvaluationContext, evaluate};
-use lexer::Lexer;
-use parser::Parser;
+use evaluator::{EvaluationContext, EvaluationError, evaluate_with_context_mut};
+use lexer::{Lexer, LexError, Span};
+use parser::{Parser, ParseError};
 use std::collections::HashMap;
+use std::io::{self, Write};
 
+// A REPL that keeps one `EvaluationContext` alive across lines, so an
+// assignment like `x = 3 + 4` is visible to every expression typed after
+// it. A single line may itself hold several `;`-separated statements, e.g.
+// `a = 5; b = a * 2; b + 1`; only the last statement's value is printed.
+// Type `exit` (or send EOF with Ctrl-D) to quit.
 fn main() {
-    let expression: String = "(3 + (5 - (3 * sqrt(16)))) * 2".to_string();
-    let mut lexer = Lexer::new(expression);
-    let tokens = lexer.tokenize();
-    let mut parser = Parser::new(tokens);
-    let ast = parser.parse();
-    match ast {
-        Some(ast) => {
-            let mut context = EvaluationContext::new(HashMap::new(), HashMap::new());
-            context.set_function("sqrt".to_string(), |args| args[0].sqrt());
-            let result = evaluate(&ast, &context);
-            println!("Steps: {:?}", result.steps);
-            println!("Result: {:?}", result.value);
-        }
-        None => {
-            println!("Parse error")
+    let mut context = EvaluationContext::new(HashMap::new(), HashMap::new());
+    context.set_function("sqrt".to_string(), Some(1), |args| args[0].sqrt());
+    context.set_function("max".to_string(), Some(2), |args| args[0].max(args[1]));
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().expect("stdout flush should not fail");
+
+        let mut line = String::new();
+        if stdin.read_line(&mut line).expect("stdin read should not fail") == 0 {
+            break; // EOF
+        }
+        let line = line.trim();
+
+        if line == "exit" {
+            break;
         }
+        if line.is_empty() {
+            continue;
+        }
+
+        let tokens = match Lexer::new(line.to_string()).tokenize() {
+            Ok(tokens) => tokens,
+            Err(error) => {
+                print_lex_error(&error, line);
+                continue;
+            }
+        };
+
+        let statements = match Parser::new(tokens).parse() {
+            Ok(statements) => statements,
+            Err(error) => {
+                print_parse_error(&error, line);
+                continue;
+            }
+        };
+
+        let result = evaluate_with_context_mut(&statements, &mut context);
+        for step in result.steps() {
+            println!("  {}", step);
+        }
+        match &result.value {
+            Ok(_) => println!("{:?}", result.value),
+            Err(error) => print_eval_error(error, line),
+        }
+    }
+}
+
+// Renders a diagnostic with a caret under the offending column, the way
+// compilers do, e.g.:
+//   (3 + (5 - (3 @ sqrt(16)))) * 2
+//               ^
+fn print_caret(message: &str, src: &str, offset: usize) {
+    let (line_no, column) = Span::new(offset).linecol_in(src);
+    let line = src.split('\n').nth(line_no).unwrap_or("");
+    println!("error: {}", message);
+    println!("{}", line);
+    println!("{}^", " ".repeat(column));
+}
+
+fn print_lex_error(error: &LexError, src: &str) {
+    match error {
+        LexError::UnexpectedChar { ch, span } => {
+            print_caret(&format!("unexpected character '{}'", ch), src, span.offset);
+        }
+        LexError::InvalidNumber { raw, span } => {
+            print_caret(&format!("invalid number literal '{}'", raw), src, span.offset);
+        }
+    }
+}
+
+fn print_parse_error(error: &ParseError, src: &str) {
+    match error {
+        ParseError::UnexpectedToken { span } => print_caret("unexpected token", src, span.offset),
+        ParseError::UnexpectedEof => println!("error: unexpected end of input"),
+        ParseError::UnmatchedParenthesis { span } => print_caret("unmatched '('", src, span.offset),
+    }
+}
+
+// Unlike `print_lex_error`/`print_parse_error`, not every `EvaluationError`
+// is tied to a single token (see `EvaluationError::span`), so this falls
+// back to a plain message for the ones that aren't.
+fn print_eval_error(error: &EvaluationError, src: &str) {
+    let message = match error {
+        EvaluationError::DivisionByZero { .. } => "division by zero".to_string(),
+        EvaluationError::UndefinedVariable { name, .. } => format!("undefined variable '{}'", name),
+        EvaluationError::UndefinedFunction { name, .. } => format!("undefined function '{}'", name),
+        EvaluationError::SyntaxError(message) => message.clone(),
+        EvaluationError::TypeError { expected, found } => format!("expected {}, found {}", expected, found),
+        EvaluationError::WrongArgumentCount { name, expected, found } => {
+            format!("'{}' expected {} argument(s), found {}", name, expected, found)
+        }
+    };
+
+    match error.span() {
+        Some(span) => print_caret(&message, src, span.offset),
+        None => println!("error: {}", message),
     }
 }
@@ -1,5 +1,18 @@
 /* The parser file*/
 
+use crate::evaluator::{ASTNode, ExpressionTokens};
+use crate::lexer::Span;
+
+// Everything that can go wrong while assembling tokens into an AST. Each
+// variant carries the span of the token the parser was looking at, copied
+// straight from the lexer's spanned token stream.
+#[derive(Debug)]
+pub enum ParseError {
+    UnexpectedToken { span: Span },
+    UnexpectedEof,
+    UnmatchedParenthesis { span: Span },
+}
+
 
 /* 
 * This struct details the BindPower which consists of:
@@ -12,12 +25,12 @@
 *   
 *   Both, lbp and rbp use the type i32 (Which is an 32-bit signed integer type as in: https://doc.rust-lang.org/std/primitive.i32.html)
 *
+*   No `BindPower` value is ever actually constructed: `get_bind_power` and
+*   get_prefix_power` return the (operator, lbp, rbp) triple described above
+*   directly as `(i32, i32)`/`i32`, so `BindPower` itself is just a namespace
+*   for those two functions.
 * */
-struct BindPower {
-    operator: char,
-    lbp: i32,
-    rbp: i32,
-}
+struct BindPower;
 
 /*
 * This struct details the Parser which consists of:
@@ -71,65 +84,145 @@ struct BindPower {
 * know the position and the tokens akin of that position on the evaluator.
 * */
 
-struct Parser {
+pub(crate) struct Parser {
     position: usize,
-    tokens: Vec<ExpressionTokens>
+    tokens: Vec<(ExpressionTokens, Span)>,
 }
 
 impl BindPower{
     fn get_bind_power(ch: char) -> Option<(i32, i32)> {
         match ch {
-            '+' | '-' => {
-                return Some((10, 9))
-            },
-            '*' | '/' => {
-                return Some((20, 19))
-            },
+            // `||`. Loosest of all: `a && b || c && d` groups as
+            // `(a && b) || (c && d)`. The right power (2) is not lower than
+            // the left (1), which is what makes same-precedence chains like
+            // `a || b || c` left-associative in this parser (see the '^'
+            // comment below for why that relationship flips for
+            // right-associative operators).
+            '|' => Some((1, 2)),
+            // `&&`. Binds tighter than `||`, looser than comparisons.
+            '&' => Some((3, 4)),
+            // `==`, `>`, `<`. Binds tighter than `&&`/`||`, looser than
+            // arithmetic, so `1 + 2 > 2` parses as `(1 + 2) > 2`.
+            '=' | '>' | '<' => Some((5, 6)),
+            '+' | '-' => Some((10, 9)),
+            '*' | '/' => Some((20, 19)),
+            // Binds tighter than '*'/'/' so `2 + 3 ^ 2` parses as `2 + (3 ^ 2)`.
+            // The left power (31) is *higher* than the right power (30),
+            // which in this parser's recursion makes the next `^` to the
+            // right win a tie against the current one, giving the
+            // right-associativity `2 ^ 3 ^ 2 == 2 ^ (3 ^ 2)`.
+            '^' => Some((31, 30)),
             _ => None,
-        }  
+        }
     }
 
+    // Binding power for a prefix (unary) operator, e.g. the '-' in `-5`.
+    // Chosen tighter than binary '+'/'-' (10) and '*'/'/' (20) so that
+    // `-3 * 4` parses as `(-3) * 4` rather than `-(3 * 4)`, but looser than
+    // the right binding power `^` will use once it lands, so that `-2 ^ 2`
+    // still parses as `-(2 ^ 2)`.
+    fn get_prefix_power(ch: char) -> Option<i32> {
+        match ch {
+            '+' | '-' => Some(25),
+            _ => None,
+        }
+    }
 }
 
 impl Parser {
-    fn new(tokens: Vec<ExpressionTokens>) -> Self {
+    pub(crate) fn new(tokens: Vec<(ExpressionTokens, Span)>) -> Self {
         Parser { position: 0, tokens }
     }
 
     fn peek(&self) -> Option<&ExpressionTokens> {
-        self.tokens.get(self.position)
+        self.tokens.get(self.position).map(|(token, _)| token)
     }
-    
+
     fn advance(&mut self) {
         self.position += 1;
     }
 
-    fn parse_expression(&mut self, min_bp: i32) -> Option<ASTNode>{
+    // The span of the token at the cursor, or of the end of the last token
+    // once the cursor has run past the end of input (so an "unexpected eof"
+    // diagnostic still has somewhere sensible to point at).
+    fn here(&self) -> Span {
+        match self.tokens.get(self.position) {
+            Some((_, span)) => *span,
+            None => self.tokens.last().map_or(Span::new(0), |(_, span)| *span),
+        }
+    }
+
+    fn parse_expression(&mut self, min_bp: i32) -> Result<ASTNode, ParseError> {
         let mut left = match self.peek() {
             Some(ExpressionTokens::Number(token)) => {
+                let span = self.here();
+                let value = *token;
                 self.advance();
-                ASTNode::Number(*token)
+                ASTNode::Number(value, span)
+            },
+            Some(ExpressionTokens::Function(token)) => {
+                let name = token.clone();
+                let span = self.here();
+                self.advance();
+                match self.peek() {
+                    Some(ExpressionTokens::LeftParenthesis) => {
+                        self.advance();
+                        self.parse_call_arguments(name, span)?
+                    }
+                    _ => return Err(ParseError::UnexpectedToken { span: self.here() }),
+                }
+            },
+            Some(ExpressionTokens::Variable(token)) if token == "true" || token == "false" => {
+                let value = token == "true";
+                self.advance();
+                ASTNode::Boolean(value)
             },
             Some(ExpressionTokens::Variable(token)) => {
+                let name = token.clone();
+                let span = self.here();
                 self.advance();
-                ASTNode::Variable(token.clone())
+
+                // The lexer now classifies an identifier directly followed
+                // by '(' as `Function` instead of `Variable`, so this branch
+                // is just a defensive fallback for a `Variable` token that
+                // still turns out to be a call.
+                if let Some(ExpressionTokens::LeftParenthesis) = self.peek() {
+                    self.advance();
+                    self.parse_call_arguments(name, span)?
+                } else {
+                    ASTNode::Variable(name, span)
+                }
             },
             Some(ExpressionTokens::LeftParenthesis) => {
+                let open = self.here();
                 self.advance();
                 let inner = self.parse_expression(0)?;
                 match self.peek(){
                     Some(ExpressionTokens::RightParenthesis) => self.advance(),
-                    _ => return None,
+                    _ => return Err(ParseError::UnmatchedParenthesis { span: open }),
                 }
                 inner
             },
-            _ => {
-                return None
+            Some(ExpressionTokens::Operator(token)) if BindPower::get_prefix_power(*token).is_some() => {
+                let op = *token;
+                let prefix_rbp = BindPower::get_prefix_power(op).expect("checked above");
+                self.advance();
+                let operand = self.parse_expression(prefix_rbp)?;
+                ASTNode::UnaryOperator {
+                    operator: op,
+                    operand: Box::new(operand),
+                }
+            },
+            Some(_) => {
+                return Err(ParseError::UnexpectedToken { span: self.here() })
+            }
+            None => {
+                return Err(ParseError::UnexpectedEof)
             }
         };
-            
+
         loop{
-            
+
             let token = self.peek();
             match token {
                 Some(ExpressionTokens::Operator(token)) => {
@@ -142,6 +235,7 @@ impl Parser {
                     }
 
                     let op = *token;
+                    let op_span = self.here();
                     self.advance();
 
                     let right = self.parse_expression(rbp)?;
@@ -150,16 +244,90 @@ impl Parser {
                         operator: op,
                         left: Box::new(left),
                         right: Box::new(right),
+                        span: op_span,
                     };
 
                 },
                 _ => break
             }
         }
-        Some(left)
+        Ok(left)
     }
 
-    fn parse(&mut self) -> Option<ASTNode> {
+    // Parses a single statement: either an assignment (a bare `Variable`
+    // immediately followed by `=`) or a plain expression.
+    fn parse_statement(&mut self) -> Result<ASTNode, ParseError> {
+        if let Some(ExpressionTokens::Variable(name)) = self.peek() {
+            if let Some((ExpressionTokens::Assign, _)) = self.tokens.get(self.position + 1) {
+                let name = name.clone();
+                self.advance();
+                self.advance();
+                let value = self.parse_expression(0)?;
+                return Ok(ASTNode::Assignment { name, value: Box::new(value) });
+            }
+        }
+
         self.parse_expression(0)
     }
+
+    // Parses a `;`-separated sequence of statements, e.g.
+    // `a = 5; b = a * 2; b + 1`. A trailing `;` (or an empty input) is
+    // allowed and simply produces no further statements.
+    pub(crate) fn parse(&mut self) -> Result<Vec<ASTNode>, ParseError> {
+        let mut statements = Vec::new();
+
+        if self.peek().is_none() {
+            return Ok(statements);
+        }
+
+        loop {
+            statements.push(self.parse_statement()?);
+
+            match self.peek() {
+                Some(ExpressionTokens::Semicolon) => {
+                    self.advance();
+                    if self.peek().is_none() {
+                        break;
+                    }
+                }
+                Some(_) => return Err(ParseError::UnexpectedToken { span: self.here() }),
+                None => break,
+            }
+        }
+
+        Ok(statements)
+    }
+
+    // Parses the comma-separated argument list of a call whose opening '('
+    // has already been consumed. Handles zero-argument calls (`foo()`), but
+    // rejects a trailing comma (`foo(1,)`) and an unterminated call. `span`
+    // is the span of the function name itself, so an `UndefinedFunction`
+    // error can point at the call site rather than its arguments.
+    fn parse_call_arguments(&mut self, name: String, span: Span) -> Result<ASTNode, ParseError> {
+        let mut args = Vec::new();
+
+        if let Some(ExpressionTokens::RightParenthesis) = self.peek() {
+            self.advance();
+            return Ok(ASTNode::FunctionCall { name, args, span });
+        }
+
+        loop {
+            args.push(self.parse_expression(0)?);
+
+            match self.peek() {
+                Some(ExpressionTokens::Comma) => {
+                    self.advance();
+                }
+                Some(ExpressionTokens::RightParenthesis) => {
+                    self.advance();
+                    break;
+                }
+                Some(_) => return Err(ParseError::UnexpectedToken { span: self.here() }),
+                None => return Err(ParseError::UnexpectedEof),
+            }
+        }
+
+        Ok(ASTNode::FunctionCall { name, args, span })
+    }
 }
+